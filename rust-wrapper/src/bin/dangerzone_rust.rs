@@ -125,30 +125,61 @@ fn convert_document(
         vec![]
     };
 
-    let mut child = runner.run_with_input(container_image, command, &extra_args, &input_data)?;
-
-    // Read pixel stream from container stdout
-    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-    let mut stream_reader = PixelStreamReader::new(stdout);
-    let pages = stream_reader.read_all_pages()?;
-
-    // Wait for container to finish
-    let status = child.wait()?;
-    if !status.success() {
-        return Err(format!("Container exited with status: {}", status).into());
+    // Stream stdin/stdout/stderr concurrently to avoid deadlocking on a full pipe buffer
+    let mut streaming = runner.run_streaming(container_image, command, &extra_args, input_data)?;
+
+    // Read the pixel stream from container stdout. If the container dies mid-stream, fall
+    // back to reconstructing from whatever pages were successfully decoded.
+    let stream_reader = PixelStreamReader::new(&mut streaming.stdout);
+    let (pages, had_stream_error) = read_pages_best_effort(stream_reader)?;
+
+    // A container that's killed mid-stream (e.g. OOM) exits non-zero or without a status
+    // code at all, but if we already salvaged pages from the partial stream, that exit
+    // status isn't worth aborting over: log it and reconstruct what we have.
+    match streaming.wait_success() {
+        Ok(()) => {}
+        Err(e) if had_stream_error && !pages.is_empty() => {
+            eprintln!("Warning: container exited abnormally ({e}); proceeding with {} salvaged page(s)", pages.len());
+        }
+        Err(e) => return Err(e.into()),
     }
 
-    // Reconstruct PDF from pixels
+    // Reconstruct the PDF and verify it round-trips before writing it to disk. Keep only
+    // the page dimensions on the side rather than cloning the full pages (pixels included),
+    // since that's all verify_output checks.
+    let expected_dimensions: Vec<(u16, u16)> = pages.iter().map(|p| (p.width, p.height)).collect();
     let reconstructor = PdfReconstructor::new();
     let pdf_data = reconstructor.reconstruct(pages)?;
+    reconstructor.verify_output(&pdf_data, &expected_dimensions)?;
 
-    // Write output PDF
     let mut output_file = File::create(output_path)?;
     output_file.write_all(&pdf_data)?;
 
     Ok(())
 }
 
+/// Reads all pages from the stream, recovering whatever pages were successfully decoded
+/// if the stream fails partway through (e.g. the container was killed mid-conversion).
+///
+/// Returns whether the stream ended in a recovered `Partial` error alongside the pages, so
+/// the caller knows a subsequent non-zero container exit is expected, not a fresh failure.
+fn read_pages_best_effort<R: std::io::Read>(
+    mut stream_reader: PixelStreamReader<R>,
+) -> Result<(Vec<dangerzone_rust::stream_reader::PageData>, bool), Box<dyn std::error::Error>> {
+    match stream_reader.read_all_pages() {
+        Ok(pages) => Ok((pages, false)),
+        Err(dangerzone_rust::StreamError::Partial { pages_read, source }) => {
+            eprintln!(
+                "Warning: pixel stream failed after {} page(s) ({}); reconstructing a partial document",
+                pages_read.len(),
+                source
+            );
+            Ok((pages_read, true))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
 fn generate_output_filename(input_path: &Path) -> PathBuf {
     let mut output = input_path.to_path_buf();
 
@@ -2,8 +2,9 @@
 //!
 //! This module provides functionality to run containers and pass data to them.
 
-use std::io::{self, Write};
-use std::process::{Child, Command, Stdio};
+use std::io::{self, Read, Write};
+use std::process::{Child, ChildStdout, Command, ExitStatus, Stdio};
+use std::thread;
 
 /// Errors that can occur during container operations.
 #[derive(Debug, thiserror::Error)]
@@ -11,11 +12,17 @@ pub enum ContainerError {
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 
-    #[error("Container execution failed: {0}")]
-    ExecutionFailed(String),
+    #[error("container exited with code {code:?}: {stderr}")]
+    ExecutionFailed { code: Option<i32>, stderr: String },
+
+    #[error("failed to set up container: {0}")]
+    SetupFailed(String),
 
     #[error("Invalid container name: {0}")]
     InvalidName(String),
+
+    #[error("failed to write input to container stdin: {0}")]
+    StdinWrite(String),
 }
 
 /// Runs containers and manages their execution.
@@ -64,7 +71,7 @@ impl ContainerRuntime {
             return Ok(ContainerRuntime::Docker);
         }
 
-        Err(ContainerError::ExecutionFailed(
+        Err(ContainerError::SetupFailed(
             "No container runtime (podman or docker) found".to_string(),
         ))
     }
@@ -158,6 +165,48 @@ impl ContainerRunner {
         Ok(child)
     }
 
+    /// Executes a container and pumps its stdin/stdout/stderr on dedicated threads.
+    ///
+    /// `run_with_input` writes the whole input before returning, which deadlocks once the
+    /// container's stdout pipe buffer fills while it's blocked writing pixel data and we're
+    /// still blocked writing stdin: neither side can make progress. This spawns a writer
+    /// thread to push `input_data` to stdin and a reader thread to drain stderr, so the
+    /// caller can read the returned stdout handle concurrently without risk of deadlock.
+    pub fn run_streaming(
+        &self,
+        image: &str,
+        command: &[&str],
+        extra_args: &[&str],
+        input_data: Vec<u8>,
+    ) -> Result<StreamingChild, ContainerError> {
+        let mut child = self.run(image, command, extra_args)?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| ContainerError::SetupFailed("failed to capture child stdin".to_string()))?;
+        let stdout = child.stdout.take().ok_or_else(|| {
+            ContainerError::SetupFailed("failed to capture child stdout".to_string())
+        })?;
+        let mut stderr = child.stderr.take().ok_or_else(|| {
+            ContainerError::SetupFailed("failed to capture child stderr".to_string())
+        })?;
+
+        let writer_handle = thread::spawn(move || stdin.write_all(&input_data));
+        let stderr_handle = thread::spawn(move || {
+            let mut captured = String::new();
+            let _ = stderr.read_to_string(&mut captured);
+            captured
+        });
+
+        Ok(StreamingChild {
+            child,
+            stdout,
+            writer_handle,
+            stderr_handle,
+        })
+    }
+
     /// Gets the container name.
     pub fn container_name(&self) -> &str {
         &self.container_name
@@ -169,6 +218,56 @@ impl ContainerRunner {
     }
 }
 
+/// A container started with [`ContainerRunner::run_streaming`].
+///
+/// `stdout` is available for the caller to read concurrently (e.g. via
+/// [`PixelStreamReader`](crate::stream_reader::PixelStreamReader)) while the stdin-writer
+/// and stderr-capture threads run in the background.
+pub struct StreamingChild {
+    child: Child,
+    pub stdout: ChildStdout,
+    writer_handle: thread::JoinHandle<io::Result<()>>,
+    stderr_handle: thread::JoinHandle<String>,
+}
+
+impl StreamingChild {
+    /// Waits for the container to exit, joining the stdin-writer and stderr-capture threads.
+    ///
+    /// Returns the exit status alongside the captured stderr text. A failure writing stdin
+    /// is surfaced as [`ContainerError::StdinWrite`] rather than silently ignored.
+    pub fn wait(mut self) -> Result<(ExitStatus, String), ContainerError> {
+        let status = self.child.wait()?;
+
+        match self.writer_handle.join() {
+            Ok(write_result) => {
+                write_result.map_err(|e| ContainerError::StdinWrite(e.to_string()))?
+            }
+            Err(_) => {
+                return Err(ContainerError::StdinWrite(
+                    "stdin writer thread panicked".to_string(),
+                ))
+            }
+        }
+
+        let stderr = self.stderr_handle.join().unwrap_or_default();
+        Ok((status, stderr))
+    }
+
+    /// Like [`wait`](Self::wait), but treats a non-zero exit as an error carrying the
+    /// exit code and captured stderr, so callers don't have to re-check `status.success()`.
+    pub fn wait_success(self) -> Result<(), ContainerError> {
+        let (status, stderr) = self.wait()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(ContainerError::ExecutionFailed {
+                code: status.code(),
+                stderr,
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,4 +331,49 @@ mod tests {
         // It's okay if it fails in environments without container runtimes
         let _ = ContainerRuntime::detect();
     }
+
+    #[test]
+    fn test_invalid_container_name_run_streaming() {
+        let runner = ContainerRunner::new("".to_string());
+        let result = runner.run_streaming("alpine:latest", &["cat"], &[], Vec::new());
+        assert!(matches!(result, Err(ContainerError::InvalidName(_))));
+    }
+
+    #[test]
+    #[ignore] // Requires podman/docker to be installed
+    fn test_run_streaming_round_trip() {
+        let runner = ContainerRunner::new("test-container-streaming".to_string());
+        let input = b"hello world".to_vec();
+        let result = runner.run_streaming("alpine:latest", &["cat"], &[], input.clone());
+
+        if let Ok(mut streaming) = result {
+            let mut output = Vec::new();
+            streaming.stdout.read_to_end(&mut output).unwrap();
+            let (status, stderr) = streaming.wait().unwrap();
+            assert!(status.success());
+            assert!(stderr.is_empty());
+            assert_eq!(output, input);
+        }
+    }
+
+    #[test]
+    #[ignore] // Requires podman/docker to be installed
+    fn test_run_streaming_wait_success_surfaces_nonzero_exit() {
+        let runner = ContainerRunner::new("test-container-streaming-failure".to_string());
+        let result = runner.run_streaming(
+            "alpine:latest",
+            &["sh", "-c", "echo boom >&2; exit 7"],
+            &[],
+            Vec::new(),
+        );
+
+        if let Ok(mut streaming) = result {
+            let mut output = Vec::new();
+            streaming.stdout.read_to_end(&mut output).unwrap();
+            assert!(matches!(
+                streaming.wait_success(),
+                Err(ContainerError::ExecutionFailed { code: Some(7), .. })
+            ));
+        }
+    }
 }
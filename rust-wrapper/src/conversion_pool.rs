@@ -0,0 +1,300 @@
+//! Parallel multi-document conversion pipeline.
+//!
+//! Converts many documents concurrently, each in its own isolated container, bounded
+//! by a fixed worker count, and reports each document's progress independently so a
+//! single failing conversion doesn't abort the rest of the batch. Modeled on a
+//! device-flashing pool that drives many independent targets in parallel.
+
+use crate::container::ContainerRunner;
+use crate::pdf_reconstructor::PdfReconstructor;
+use crate::stream_reader::PixelStreamReader;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Monotonic counter disambiguating container names for concurrently-submitted jobs,
+/// since two submitted paths can share a basename across different directories.
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Errors that can occur setting up the conversion pool.
+#[derive(Debug, thiserror::Error)]
+pub enum ConversionPoolError {
+    #[error("failed to raise the open file descriptor limit: {0}")]
+    RlimitFailed(String),
+}
+
+/// Progress of a single document as it moves through the conversion pipeline.
+#[derive(Debug, Clone)]
+pub enum ConversionProgress {
+    Queued,
+    Converting,
+    StreamingPixels,
+    Reconstructing,
+    Done { output_path: PathBuf },
+    Failed { error: String },
+}
+
+/// A progress update for one submitted document.
+#[derive(Debug, Clone)]
+pub struct ConversionUpdate {
+    pub input_path: PathBuf,
+    pub progress: ConversionProgress,
+}
+
+/// Converts many documents concurrently, each in its own isolated container.
+pub struct ConversionPool {
+    job_tx: Sender<PathBuf>,
+    update_tx: Sender<ConversionUpdate>,
+    update_rx: Receiver<ConversionUpdate>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl ConversionPool {
+    /// Creates a pool with `workers` concurrent conversions, running `container_image`.
+    ///
+    /// Raises the process's soft `RLIMIT_NOFILE` proportional to `workers * 3 + headroom`,
+    /// since each concurrent conversion holds piped stdin/stdout/stderr file descriptors.
+    pub fn new(workers: usize, container_image: String) -> Result<Self, ConversionPoolError> {
+        Self::raise_file_descriptor_limit(workers)?;
+
+        let (job_tx, job_rx) = mpsc::channel::<PathBuf>();
+        let (update_tx, update_rx) = mpsc::channel::<ConversionUpdate>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+
+        let mut worker_handles = Vec::with_capacity(workers);
+        for _ in 0..workers {
+            let job_rx = Arc::clone(&job_rx);
+            let update_tx = update_tx.clone();
+            let container_image = container_image.clone();
+
+            worker_handles.push(thread::spawn(move || loop {
+                let input_path = match job_rx.lock().unwrap().recv() {
+                    Ok(path) => path,
+                    Err(_) => break, // job_tx dropped (shutdown), no more jobs will arrive
+                };
+                convert_one(&input_path, &container_image, &update_tx);
+            }));
+        }
+
+        Ok(ConversionPool {
+            job_tx,
+            update_tx,
+            update_rx,
+            workers: worker_handles,
+        })
+    }
+
+    /// Queues a document for conversion. Returns immediately; progress arrives via `results`.
+    pub fn submit(&self, path: PathBuf) {
+        let _ = self.update_tx.send(ConversionUpdate {
+            input_path: path.clone(),
+            progress: ConversionProgress::Queued,
+        });
+        let _ = self.job_tx.send(path);
+    }
+
+    /// Returns the receiving end of the per-document progress channel. While the pool is
+    /// still accepting work, callers should drain it with `.try_iter()` (non-blocking) —
+    /// the pool holds a sender for as long as it's alive, so `.iter()` here would block
+    /// forever. To block until every update has arrived, call [`shutdown`](Self::shutdown)
+    /// instead and drain the receiver it returns.
+    pub fn results(&self) -> &Receiver<ConversionUpdate> {
+        &self.update_rx
+    }
+
+    /// Stops accepting new jobs, waits for every in-flight conversion to finish, and
+    /// returns the progress receiver for the caller to drain to completion.
+    ///
+    /// Consumes the pool so its `job_tx` and `update_tx` are dropped; each worker's own
+    /// `update_tx` clone then drops once it observes the job channel close after finishing
+    /// its current job. Only once every sender has gone does `.iter()` on the returned
+    /// receiver terminate.
+    pub fn shutdown(self) -> Receiver<ConversionUpdate> {
+        let ConversionPool {
+            job_tx,
+            update_tx,
+            update_rx,
+            workers,
+        } = self;
+        drop(job_tx);
+        drop(update_tx);
+        for worker in workers {
+            let _ = worker.join();
+        }
+        update_rx
+    }
+
+    #[cfg(unix)]
+    fn raise_file_descriptor_limit(workers: usize) -> Result<(), ConversionPoolError> {
+        const HEADROOM: u64 = 64;
+        let desired = (workers as u64) * 3 + HEADROOM;
+
+        unsafe {
+            let mut limits: libc::rlimit = std::mem::zeroed();
+            if libc::getrlimit(libc::RLIMIT_NOFILE, &mut limits) != 0 {
+                return Err(ConversionPoolError::RlimitFailed(
+                    std::io::Error::last_os_error().to_string(),
+                ));
+            }
+
+            if limits.rlim_cur < desired {
+                let ceiling = if limits.rlim_max == libc::RLIM_INFINITY {
+                    desired
+                } else {
+                    desired.min(limits.rlim_max)
+                };
+                limits.rlim_cur = ceiling;
+                if libc::setrlimit(libc::RLIMIT_NOFILE, &limits) != 0 {
+                    return Err(ConversionPoolError::RlimitFailed(
+                        std::io::Error::last_os_error().to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn raise_file_descriptor_limit(_workers: usize) -> Result<(), ConversionPoolError> {
+        Ok(())
+    }
+}
+
+/// Runs one document through the container + pixel-stream + reconstruction pipeline,
+/// reporting progress as it goes.
+fn convert_one(input_path: &Path, container_image: &str, update_tx: &Sender<ConversionUpdate>) {
+    let send = |progress: ConversionProgress| {
+        let _ = update_tx.send(ConversionUpdate {
+            input_path: input_path.to_path_buf(),
+            progress,
+        });
+    };
+
+    send(ConversionProgress::Converting);
+
+    let result: Result<PathBuf, String> = (|| {
+        let input_data = std::fs::read(input_path).map_err(|e| e.to_string())?;
+
+        let job_id = NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed);
+        let container_name = format!(
+            "dangerzone-rust-pool-{}-{}-{}",
+            std::process::id(),
+            job_id,
+            input_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("input")
+        );
+        let runner =
+            ContainerRunner::with_auto_runtime(container_name).map_err(|e| e.to_string())?;
+
+        let command = &[
+            "/usr/bin/python3",
+            "-m",
+            "dangerzone.conversion.doc_to_pixels",
+        ];
+        let mut streaming = runner
+            .run_streaming(container_image, command, &[], input_data)
+            .map_err(|e| e.to_string())?;
+
+        send(ConversionProgress::StreamingPixels);
+        let mut stream_reader = PixelStreamReader::new(&mut streaming.stdout);
+
+        // Collect pages one at a time; if the stream fails partway through, keep whatever
+        // pages were already decoded rather than discarding the batch's partial progress.
+        let mut pages = Vec::new();
+        let mut stream_error = None;
+        match stream_reader.pages() {
+            Ok(iter) => {
+                for page in iter {
+                    match page {
+                        Ok(p) => pages.push(p),
+                        Err(e) => {
+                            stream_error = Some(e);
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => stream_error = Some(e),
+        }
+        if let Some(ref e) = stream_error {
+            if pages.is_empty() {
+                return Err(e.to_string());
+            }
+        }
+
+        // A container killed mid-stream (e.g. OOM) exits non-zero or without a status code
+        // at all; if pages were already salvaged from the partial stream, that exit status
+        // isn't worth aborting the batch entry over, so only hard-fail when nothing was
+        // salvaged.
+        if let Err(e) = streaming.wait_success() {
+            if stream_error.is_none() || pages.is_empty() {
+                return Err(e.to_string());
+            }
+        }
+
+        send(ConversionProgress::Reconstructing);
+        let reconstructor = PdfReconstructor::new();
+        let output_path = output_path_for(input_path);
+        let output_file = std::fs::File::create(&output_path).map_err(|e| e.to_string())?;
+        reconstructor
+            .reconstruct_to_writer(pages, output_file)
+            .map_err(|e| e.to_string())?;
+
+        Ok(output_path)
+    })();
+
+    match result {
+        Ok(output_path) => send(ConversionProgress::Done { output_path }),
+        Err(error) => send(ConversionProgress::Failed { error }),
+    }
+}
+
+/// Derives the `<stem>-safe.pdf` output path alongside the input file.
+fn output_path_for(input_path: &Path) -> PathBuf {
+    let mut output = input_path.to_path_buf();
+    let stem = input_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    output.set_file_name(format!("{}-safe.pdf", stem));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path_for() {
+        let path = output_path_for(Path::new("/tmp/document.docx"));
+        assert_eq!(path, PathBuf::from("/tmp/document-safe.pdf"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_raise_file_descriptor_limit_does_not_error() {
+        assert!(ConversionPool::raise_file_descriptor_limit(4).is_ok());
+    }
+
+    #[test]
+    fn test_submit_and_shutdown_drains_results() {
+        let pool = ConversionPool::new(2, "unused:image".to_string()).unwrap();
+        pool.submit(PathBuf::from("/nonexistent/input.docx"));
+
+        // shutdown() joins the worker threads before handing back the receiver, so this
+        // `.iter()` is guaranteed to see every update already sent and then terminate.
+        let updates: Vec<ConversionUpdate> = pool.shutdown().iter().collect();
+
+        assert!(updates
+            .iter()
+            .any(|u| matches!(u.progress, ConversionProgress::Queued)));
+        assert!(updates
+            .iter()
+            .any(|u| matches!(u.progress, ConversionProgress::Failed { .. })));
+    }
+}
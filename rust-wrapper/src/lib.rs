@@ -4,12 +4,14 @@
 //! and reconstructing PDFs from streamed pixel data.
 
 pub mod container;
+pub mod conversion_pool;
 pub mod pdf_reconstructor;
 pub mod stream_reader;
 
-pub use container::{ContainerError, ContainerRunner};
+pub use container::{ContainerError, ContainerRunner, StreamingChild};
+pub use conversion_pool::{ConversionPool, ConversionPoolError, ConversionProgress};
 pub use pdf_reconstructor::{PdfError, PdfReconstructor};
-pub use stream_reader::{PixelStreamReader, StreamError};
+pub use stream_reader::{ColorMode, PixelStreamReader, StreamCodec, StreamError};
 
 #[cfg(test)]
 mod tests {
@@ -2,13 +2,17 @@
 //!
 //! This module converts pixel data back into a PDF document.
 
-use crate::stream_reader::PageData;
+use crate::stream_reader::{ColorMode, PageData};
 use printpdf::*;
 use std::io::BufWriter;
 
 /// DPI used for PDF reconstruction (must match DEFAULT_DPI from Python code).
 const DEFAULT_DPI: f32 = 150.0;
 
+/// Allowed slack, in points, when comparing a verified PDF's MediaBox against the
+/// dimensions computed from the original pixel data (rounding in the PDF writer).
+const VERIFY_TOLERANCE_PT: f32 = 1.0;
+
 /// Errors that can occur during PDF reconstruction.
 #[derive(Debug, thiserror::Error)]
 pub enum PdfError {
@@ -25,14 +29,118 @@ pub enum PdfError {
     ImageCreation(String),
 }
 
+/// Page image compression strategy for embedded page images.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Compression {
+    /// Lossless: pixels are embedded raw, with no declared image filter. printpdf has no
+    /// image XObject filter for a lossless format other than the already-compressed ones
+    /// (`DCT`/`JPX`/`Lzw`/`Ascii85`), so the size reduction comes from the PDF writer's own
+    /// stream-level Flate compression, applied to every object (including this one) on save.
+    #[default]
+    Flate,
+    /// Lossy JPEG encoding at the given quality, 1-100 (`/Filter /DCTDecode`).
+    Jpeg { quality: u8 },
+}
+
+/// Document-level metadata written into the reconstructed PDF's Info dictionary.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub creation_date: Option<OffsetDateTime>,
+    pub mod_date: Option<OffsetDateTime>,
+}
+
+/// A single bookmark/outline entry pointing at a 0-based page index.
+#[derive(Debug, Clone)]
+pub struct OutlineEntry {
+    pub label: String,
+    pub page_index: usize,
+}
+
+/// Default document title, used when no `DocumentMetadata::title` is set.
+const DEFAULT_TITLE: &str = "Dangerzone Safe PDF";
+
+/// Page orientation for paper sizes that support both.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Output page layout: either size each page to its source image, or fit every
+/// page onto a uniform, standard paper size.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PaperSize {
+    /// Each page keeps the exact pixel dimensions of its source image (default).
+    #[default]
+    Native,
+    /// ISO A4, 210 x 297 mm, portrait.
+    A4,
+    /// US Letter, 8.5 x 11 in, in the given orientation.
+    Letter { orientation: Orientation },
+}
+
+impl PaperSize {
+    /// Returns the physical page size in mm, or `None` for `Native`.
+    fn dimensions_mm(&self) -> Option<(f32, f32)> {
+        match self {
+            PaperSize::Native => None,
+            PaperSize::A4 => Some((210.0, 297.0)),
+            PaperSize::Letter { orientation } => {
+                let (width, height) = (215.9, 279.4);
+                Some(match orientation {
+                    Orientation::Portrait => (width, height),
+                    Orientation::Landscape => (height, width),
+                })
+            }
+        }
+    }
+}
+
+/// Page margins in mm, applied when fitting a source image onto a standard paper size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Margins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Margins {
+            top: 10.0,
+            right: 10.0,
+            bottom: 10.0,
+            left: 10.0,
+        }
+    }
+}
+
 /// Reconstructs PDFs from pixel data.
 pub struct PdfReconstructor {
     dpi: f32,
+    compression: Compression,
+    metadata: DocumentMetadata,
+    outline: Vec<OutlineEntry>,
+    page_size: PaperSize,
+    margins: Margins,
 }
 
 impl Default for PdfReconstructor {
     fn default() -> Self {
-        PdfReconstructor { dpi: DEFAULT_DPI }
+        PdfReconstructor {
+            dpi: DEFAULT_DPI,
+            compression: Compression::default(),
+            metadata: DocumentMetadata::default(),
+            outline: Vec::new(),
+            page_size: PaperSize::default(),
+            margins: Margins::default(),
+        }
     }
 }
 
@@ -44,7 +152,41 @@ impl PdfReconstructor {
 
     /// Creates a new PdfReconstructor with a custom DPI.
     pub fn with_dpi(dpi: f32) -> Self {
-        PdfReconstructor { dpi }
+        PdfReconstructor {
+            dpi,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the page image compression strategy, returning the updated reconstructor.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the document metadata written into the PDF's Info dictionary.
+    pub fn with_metadata(mut self, metadata: DocumentMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Sets the bookmark/outline tree, mapping a label to a 0-based page index.
+    pub fn with_outline(mut self, outline: Vec<OutlineEntry>) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    /// Sets the output page layout; `PaperSize::Native` (the default) sizes each page to
+    /// its source image, while a standard size fits every page onto a uniform paper size.
+    pub fn with_page_size(mut self, page_size: PaperSize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Sets the margins applied when fitting pages onto a standard paper size.
+    pub fn with_margins(mut self, margins: Margins) -> Self {
+        self.margins = margins;
+        self
     }
 
     /// Converts pixels to PDF dimensions in points (1/72 inch).
@@ -57,8 +199,35 @@ impl PdfReconstructor {
         points * 0.352778
     }
 
-    /// Reconstructs a PDF from pixel data pages.
+    /// Returns the PDF page size in mm for `page`: its native pixel dimensions under
+    /// `PaperSize::Native`, or the configured standard paper size otherwise.
+    fn page_dimensions_mm(&self, page: &PageData) -> (f32, f32) {
+        self.page_size.dimensions_mm().unwrap_or_else(|| {
+            let width_pt = self.pixels_to_points(page.width);
+            let height_pt = self.pixels_to_points(page.height);
+            (Self::points_to_mm(width_pt), Self::points_to_mm(height_pt))
+        })
+    }
+
+    /// Reconstructs a PDF from pixel data pages, returning the encoded bytes.
+    ///
+    /// Thin wrapper over [`reconstruct_to_writer`](Self::reconstruct_to_writer) that buffers
+    /// the output in memory; prefer that method when writing directly to a file or socket.
     pub fn reconstruct(&self, pages: Vec<PageData>) -> Result<Vec<u8>, PdfError> {
+        let mut buf = Vec::new();
+        self.reconstruct_to_writer(pages, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reconstructs a PDF from pixel data pages, saving directly into `writer`.
+    ///
+    /// Avoids buffering the whole PDF in memory on top of the decoded pixel pages, matching
+    /// the streaming `create_for_stream` pattern used by cairo's PDF/PNG surfaces.
+    pub fn reconstruct_to_writer<W: std::io::Write>(
+        &self,
+        pages: Vec<PageData>,
+        writer: W,
+    ) -> Result<(), PdfError> {
         if pages.is_empty() {
             return Err(PdfError::NoPages);
         }
@@ -72,19 +241,27 @@ impl PdfReconstructor {
             });
         }
 
-        let first_width_pt = self.pixels_to_points(first_page.width);
-        let first_height_pt = self.pixels_to_points(first_page.height);
+        let (first_width_mm, first_height_mm) = self.page_dimensions_mm(first_page);
 
         // Create a new PDF document with the first page
+        let title = self.metadata.title.as_deref().unwrap_or(DEFAULT_TITLE);
         let (doc, page_idx, layer_idx) = PdfDocument::new(
-            "Dangerzone Safe PDF",
-            Mm(Self::points_to_mm(first_width_pt)),
-            Mm(Self::points_to_mm(first_height_pt)),
+            title,
+            Mm(first_width_mm),
+            Mm(first_height_mm),
             "Layer 1",
         );
 
         // Add the first page content
-        self.add_page_image(&doc, page_idx, layer_idx, first_page)?;
+        self.add_page_image(
+            &doc,
+            page_idx,
+            layer_idx,
+            first_page,
+            first_width_mm,
+            first_height_mm,
+        )?;
+        let mut page_indices = vec![page_idx];
 
         // Add remaining pages
         for page in pages.iter().skip(1) {
@@ -95,24 +272,68 @@ impl PdfReconstructor {
                 });
             }
 
-            let width_pt = self.pixels_to_points(page.width);
-            let height_pt = self.pixels_to_points(page.height);
+            let (width_mm, height_mm) = self.page_dimensions_mm(page);
 
-            let (page_idx, layer_idx) = doc.add_page(
-                Mm(Self::points_to_mm(width_pt)),
-                Mm(Self::points_to_mm(height_pt)),
-                "Layer 1",
-            );
+            let (page_idx, layer_idx) = doc.add_page(Mm(width_mm), Mm(height_mm), "Layer 1");
 
-            self.add_page_image(&doc, page_idx, layer_idx, page)?;
+            self.add_page_image(&doc, page_idx, layer_idx, page, width_mm, height_mm)?;
+            page_indices.push(page_idx);
         }
 
-        // Save the PDF to a buffer
-        let mut buf = Vec::new();
-        doc.save(&mut BufWriter::new(&mut buf))
+        let doc = self.write_metadata(doc);
+        self.write_outline(&doc, &page_indices)?;
+
+        // Save the PDF directly into the caller's writer
+        doc.save(&mut BufWriter::new(writer))
             .map_err(|e| PdfError::PdfCreation(e.to_string()))?;
 
-        Ok(buf)
+        Ok(())
+    }
+
+    /// Writes the configured `DocumentMetadata` into the PDF's Info dictionary.
+    ///
+    /// `PdfDocumentReference`'s metadata setters are self-consuming builders, so this takes
+    /// `doc` by value and hands back the (same, internally mutated) reference for the caller
+    /// to keep using.
+    fn write_metadata(&self, doc: PdfDocumentReference) -> PdfDocumentReference {
+        let mut doc = doc;
+        if let Some(author) = &self.metadata.author {
+            doc = doc.with_author(author.clone());
+        }
+        if let Some(subject) = &self.metadata.subject {
+            doc = doc.with_subject(subject.clone());
+        }
+        if let Some(keywords) = &self.metadata.keywords {
+            doc = doc.with_keywords(vec![keywords.clone()]);
+        }
+        if let Some(creator) = &self.metadata.creator {
+            doc = doc.with_creator(creator.clone());
+        }
+        if let Some(creation_date) = self.metadata.creation_date {
+            doc = doc.with_creation_date(creation_date);
+        }
+        if let Some(mod_date) = self.metadata.mod_date {
+            doc = doc.with_mod_date(mod_date);
+        }
+        doc
+    }
+
+    /// Builds the document outline (bookmark tree) from the configured entries.
+    fn write_outline(
+        &self,
+        doc: &PdfDocumentReference,
+        page_indices: &[PdfPageIndex],
+    ) -> Result<(), PdfError> {
+        for entry in &self.outline {
+            let page_idx = page_indices.get(entry.page_index).ok_or_else(|| {
+                PdfError::PdfCreation(format!(
+                    "outline entry '{}' references out-of-range page {}",
+                    entry.label, entry.page_index
+                ))
+            })?;
+            doc.add_bookmark(&entry.label, *page_idx);
+        }
+        Ok(())
     }
 
     /// Adds an image to a PDF page.
@@ -122,27 +343,56 @@ impl PdfReconstructor {
         page_idx: PdfPageIndex,
         layer_idx: PdfLayerIndex,
         page: &PageData,
+        page_width_mm: f32,
+        page_height_mm: f32,
     ) -> Result<(), PdfError> {
-        // Create image from raw RGB data
-        let image = Image::from_dynamic_image(&self.create_rgb_image(page)?);
+        // Encode the page pixels according to the configured compression strategy
+        let image = Image::from(self.build_image_xobject(page)?);
 
         // Get the current layer
         let current_layer = doc.get_page(page_idx).get_layer(layer_idx);
 
-        // Calculate dimensions in mm
-        let width_pt = self.pixels_to_points(page.width);
-        let height_pt = self.pixels_to_points(page.height);
-        let width_mm = Self::points_to_mm(width_pt);
-        let height_mm = Self::points_to_mm(height_pt);
+        // Native size of the source image in mm
+        let native_width_mm = Self::points_to_mm(self.pixels_to_points(page.width));
+        let native_height_mm = Self::points_to_mm(self.pixels_to_points(page.height));
+
+        // `scale_x`/`scale_y` are unitless multipliers on top of the image's point size at
+        // `transform.dpi` (printpdf defaults `dpi` to 300 when unset), not absolute mm
+        // values — so `dpi` must be pinned to the DPI `native_width_mm`/`native_height_mm`
+        // were computed at, and the scales must be ratios relative to that native size.
+        let (scale_x, scale_y, translate_x_mm, translate_y_mm) = match self.page_size {
+            // Native mode: the image fills the whole page at its native size, no scaling
+            // or centering needed
+            PaperSize::Native => (1.0, 1.0, 0.0, 0.0),
+            // Fixed paper size: scale to fit within the margins, preserving aspect ratio,
+            // and center the result on the page
+            _ => {
+                let available_width_mm =
+                    (page_width_mm - self.margins.left - self.margins.right).max(0.0);
+                let available_height_mm =
+                    (page_height_mm - self.margins.top - self.margins.bottom).max(0.0);
+                let scale = (available_width_mm / native_width_mm)
+                    .min(available_height_mm / native_height_mm);
+
+                let final_width_mm = native_width_mm * scale;
+                let final_height_mm = native_height_mm * scale;
+                (
+                    scale,
+                    scale,
+                    (page_width_mm - final_width_mm) / 2.0,
+                    (page_height_mm - final_height_mm) / 2.0,
+                )
+            }
+        };
 
-        // Add the image to fill the entire page
         image.add_to_layer(
             current_layer,
             ImageTransform {
-                translate_x: Some(Mm(0.0)),
-                translate_y: Some(Mm(0.0)),
-                scale_x: Some(width_mm),
-                scale_y: Some(height_mm),
+                translate_x: Some(Mm(translate_x_mm)),
+                translate_y: Some(Mm(translate_y_mm)),
+                dpi: Some(self.dpi),
+                scale_x: Some(scale_x),
+                scale_y: Some(scale_y),
                 ..Default::default()
             },
         );
@@ -150,19 +400,193 @@ impl PdfReconstructor {
         Ok(())
     }
 
-    /// Creates a DynamicImage from RGB pixel data using the image crate.
-    fn create_rgb_image(&self, page: &PageData) -> Result<::image::DynamicImage, PdfError> {
-        let img = ::image::ImageBuffer::<::image::Rgb<u8>, _>::from_raw(
-            page.width as u32,
-            page.height as u32,
-            page.pixels.clone(),
-        )
-        .ok_or(PdfError::InvalidDimensions {
+    /// Creates a DynamicImage from a page's pixel data, matching its `ColorMode`.
+    fn create_dynamic_image(&self, page: &PageData) -> Result<::image::DynamicImage, PdfError> {
+        let dims_err = || PdfError::InvalidDimensions {
             width: page.width,
             height: page.height,
-        })?;
+        };
+
+        match page.color_mode {
+            ColorMode::Grayscale => {
+                let img = ::image::ImageBuffer::<::image::Luma<u8>, _>::from_raw(
+                    page.width as u32,
+                    page.height as u32,
+                    page.pixels.clone(),
+                )
+                .ok_or_else(dims_err)?;
+                Ok(::image::DynamicImage::ImageLuma8(img))
+            }
+            ColorMode::Rgb => {
+                let img = ::image::ImageBuffer::<::image::Rgb<u8>, _>::from_raw(
+                    page.width as u32,
+                    page.height as u32,
+                    page.pixels.clone(),
+                )
+                .ok_or_else(dims_err)?;
+                Ok(::image::DynamicImage::ImageRgb8(img))
+            }
+            ColorMode::Rgba => {
+                let img = ::image::ImageBuffer::<::image::Rgba<u8>, _>::from_raw(
+                    page.width as u32,
+                    page.height as u32,
+                    page.pixels.clone(),
+                )
+                .ok_or_else(dims_err)?;
+                Ok(::image::DynamicImage::ImageRgba8(img))
+            }
+        }
+    }
+
+    /// Maps a page's `ColorMode` onto the PDF image XObject color space.
+    ///
+    /// `Rgba` maps to `Rgb`: printpdf's own `ColorSpace::Rgba` serializes as a bare
+    /// `/ColorSpace /DeviceN` name with no colorant-names/alternate-space/tint-transform
+    /// array, which isn't a conforming `DeviceN` (the PDF spec requires that to always be an
+    /// array). `build_image_xobject` always strips the alpha channel from an RGBA page
+    /// before embedding it, so the raster it writes is always 3-component RGB.
+    fn pdf_color_space(color_mode: ColorMode) -> ColorSpace {
+        match color_mode {
+            ColorMode::Grayscale => ColorSpace::Greyscale,
+            ColorMode::Rgb | ColorMode::Rgba => ColorSpace::Rgb,
+        }
+    }
+
+    /// Drops the alpha channel from interleaved RGBA bytes, keeping only RGB.
+    ///
+    /// printpdf 0.7's `SMask` support doesn't actually work: `ImageXObject`'s conversion to
+    /// a `lopdf::Stream` embeds the soft mask as a literal nested stream object inline in the
+    /// `/SMask` dictionary entry rather than as a proper indirect reference, which isn't valid
+    /// PDF syntax and corrupts the file (confirmed by round-tripping the output back through
+    /// `lopdf`, which then fails to find the image object at all). Until that's fixed
+    /// upstream, an RGBA page is flattened to opaque RGB instead — the same outcome the
+    /// `Jpeg` compression path already has, since the `image` crate's JPEG encoder silently
+    /// drops alpha too.
+    fn drop_alpha(pixels: &[u8]) -> Vec<u8> {
+        let mut rgb = Vec::with_capacity(pixels.len() / 4 * 3);
+        for pixel in pixels.chunks_exact(4) {
+            rgb.extend_from_slice(&pixel[..3]);
+        }
+        rgb
+    }
+
+    /// Encodes a page's pixels into an `ImageXObject` using the configured compression.
+    ///
+    /// `Flate` embeds the raw pixel bytes with no image filter declared, leaving the PDF
+    /// writer's own stream-level Flate compression to shrink it losslessly; `Jpeg` runs the
+    /// page through the `image` crate's JPEG encoder and embeds the already-compressed bytes
+    /// with `/Filter /DCTDecode` so they pass through untouched. Either way, an RGBA page's
+    /// alpha channel is dropped rather than embedded (see [`drop_alpha`](Self::drop_alpha)),
+    /// so the raster is always 3-component RGB.
+    fn build_image_xobject(&self, page: &PageData) -> Result<ImageXObject, PdfError> {
+        let (image_data, image_filter) = match self.compression {
+            Compression::Flate => match page.color_mode {
+                ColorMode::Rgba => (Self::drop_alpha(&page.pixels), None),
+                _ => (page.pixels.clone(), None),
+            },
+            Compression::Jpeg { quality } => {
+                let dynamic_image = self.create_dynamic_image(page)?;
+                let mut jpeg_bytes = Vec::new();
+                let encoder =
+                    ::image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_bytes, quality);
+                dynamic_image
+                    .write_with_encoder(encoder)
+                    .map_err(|e| PdfError::ImageCreation(e.to_string()))?;
+                (jpeg_bytes, Some(ImageFilter::DCT))
+            }
+        };
+
+        Ok(ImageXObject {
+            width: Px(page.width as usize),
+            height: Px(page.height as usize),
+            color_space: Self::pdf_color_space(page.color_mode),
+            bits_per_component: ColorBits::Bit8,
+            interpolate: false,
+            image_data,
+            image_filter,
+            clipping_bbox: None,
+            smask: None,
+        })
+    }
+
+    /// Re-parses a freshly generated PDF and checks it matches the pages it was built from.
+    ///
+    /// Loads `pdf_bytes` with `lopdf`, confirms the page count matches `expected_dimensions`,
+    /// and compares each page's MediaBox against the dimensions `pixels_to_points` would
+    /// produce (or, under a standard `PaperSize`, against that paper size). Intended as a post-reconstruction gate so a silently malformed PDF is caught
+    /// before it's written to disk, rather than handed to the user as a "safe" document.
+    ///
+    /// Takes each page's `(width, height)` in pixels rather than the full `PageData`, since
+    /// that's all this checks — callers that already handed their pages to
+    /// [`reconstruct`](Self::reconstruct) don't need to keep a second copy of the raw pixels
+    /// around just to call this afterward.
+    pub fn verify_output(
+        &self,
+        pdf_bytes: &[u8],
+        expected_dimensions: &[(u16, u16)],
+    ) -> Result<(), PdfError> {
+        let doc = lopdf::Document::load_from(pdf_bytes)
+            .map_err(|e| PdfError::PdfCreation(format!("verification: invalid PDF: {}", e)))?;
+
+        let pages = doc.get_pages();
+        if pages.len() != expected_dimensions.len() {
+            return Err(PdfError::PdfCreation(format!(
+                "verification: expected {} pages, found {}",
+                expected_dimensions.len(),
+                pages.len()
+            )));
+        }
+
+        for (page_id, &(expected_width, expected_height)) in pages.values().zip(expected_dimensions)
+        {
+            let page_dict = doc
+                .get_object(*page_id)
+                .and_then(lopdf::Object::as_dict)
+                .map_err(|e| PdfError::PdfCreation(format!("verification: {}", e)))?;
+
+            let media_box = page_dict
+                .get(b"MediaBox")
+                .and_then(lopdf::Object::as_array)
+                .map_err(|e| PdfError::PdfCreation(format!("verification: {}", e)))?;
+
+            let width_pt = Self::object_to_f32(&media_box[2])?;
+            let height_pt = Self::object_to_f32(&media_box[3])?;
+
+            // Under a standard paper size every page is that size, not the image's size
+            let (expected_width_pt, expected_height_pt) = match self.page_size.dimensions_mm() {
+                Some((width_mm, height_mm)) => (
+                    width_mm / Self::points_to_mm(1.0),
+                    height_mm / Self::points_to_mm(1.0),
+                ),
+                None => (
+                    self.pixels_to_points(expected_width),
+                    self.pixels_to_points(expected_height),
+                ),
+            };
+
+            if (width_pt - expected_width_pt).abs() > VERIFY_TOLERANCE_PT
+                || (height_pt - expected_height_pt).abs() > VERIFY_TOLERANCE_PT
+            {
+                return Err(PdfError::PdfCreation(format!(
+                    "verification: page MediaBox {}x{} does not match expected {}x{}",
+                    width_pt, height_pt, expected_width_pt, expected_height_pt
+                )));
+            }
+        }
+
+        Ok(())
+    }
 
-        Ok(::image::DynamicImage::ImageRgb8(img))
+    /// Converts a numeric `lopdf::Object` (Integer or Real) into an `f32`.
+    fn object_to_f32(object: &lopdf::Object) -> Result<f32, PdfError> {
+        match object {
+            lopdf::Object::Integer(i) => Ok(*i as f32),
+            lopdf::Object::Real(f) => Ok(*f),
+            other => Err(PdfError::PdfCreation(format!(
+                "verification: expected a numeric MediaBox entry, got {:?}",
+                other
+            ))),
+        }
     }
 }
 
@@ -183,6 +607,116 @@ mod tests {
         assert_eq!(reconstructor.dpi, 300.0);
     }
 
+    #[test]
+    fn test_default_compression_is_flate() {
+        let reconstructor = PdfReconstructor::new();
+        assert_eq!(reconstructor.compression, Compression::Flate);
+    }
+
+    #[test]
+    fn test_with_compression_jpeg() {
+        let reconstructor = PdfReconstructor::new().with_compression(Compression::Jpeg {
+            quality: 80,
+        });
+        assert_eq!(
+            reconstructor.compression,
+            Compression::Jpeg { quality: 80 }
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_with_jpeg_compression() {
+        let reconstructor =
+            PdfReconstructor::new().with_compression(Compression::Jpeg { quality: 75 });
+
+        let pixels = vec![
+            255, 0, 0, 255, 0, 0, 255, 0, 0, 255, 0, 0,
+        ];
+        let page = PageData::new(2, 2, pixels).unwrap();
+
+        let result = reconstructor.reconstruct(vec![page]);
+        assert!(result.is_ok());
+        assert!(result.unwrap().starts_with(b"%PDF-"));
+    }
+
+    #[test]
+    fn test_reconstruct_with_rgba_page_uses_conforming_color_space() {
+        let reconstructor = PdfReconstructor::new();
+        let pixels = vec![255u8; 2 * 2 * 4];
+        let page = PageData::with_color_mode(2, 2, ColorMode::Rgba, pixels).unwrap();
+
+        let pdf_data = reconstructor.reconstruct(vec![page]).unwrap();
+        let doc = lopdf::Document::load_from(pdf_data.as_slice()).unwrap();
+
+        let (_, page_id) = doc.get_pages().into_iter().next().unwrap();
+        let resources_ref = doc
+            .get_object(page_id)
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Resources")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let resources = doc.get_object(resources_ref).unwrap().as_dict().unwrap();
+        let xobjects = resources.get(b"XObject").unwrap().as_dict().unwrap();
+        let (_, image_ref) = xobjects.iter().next().unwrap();
+        let image_dict = doc
+            .get_object(image_ref.as_reference().unwrap())
+            .unwrap()
+            .as_stream()
+            .unwrap()
+            .dict
+            .clone();
+
+        // The embedded raster must be a real 3-component color space, never the
+        // nonconforming bare-name `DeviceN` printpdf's own `ColorSpace::Rgba` would
+        // serialize as.
+        assert_eq!(
+            image_dict.get(b"ColorSpace").unwrap().as_name().unwrap(),
+            b"DeviceRGB"
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_with_metadata_and_outline() {
+        let reconstructor = PdfReconstructor::new()
+            .with_metadata(DocumentMetadata {
+                title: Some("Safe Report".to_string()),
+                author: Some("Dangerzone".to_string()),
+                ..Default::default()
+            })
+            .with_outline(vec![
+                OutlineEntry {
+                    label: "Page 1".to_string(),
+                    page_index: 0,
+                },
+                OutlineEntry {
+                    label: "Page 2".to_string(),
+                    page_index: 1,
+                },
+            ]);
+
+        let page1 = PageData::new(2, 2, vec![255u8; 12]).unwrap();
+        let page2 = PageData::new(2, 2, vec![0u8; 12]).unwrap();
+
+        let result = reconstructor.reconstruct(vec![page1, page2]);
+        assert!(result.is_ok());
+        assert!(result.unwrap().starts_with(b"%PDF-"));
+    }
+
+    #[test]
+    fn test_reconstruct_with_outline_out_of_range() {
+        let reconstructor = PdfReconstructor::new().with_outline(vec![OutlineEntry {
+            label: "Missing".to_string(),
+            page_index: 5,
+        }]);
+
+        let page = PageData::new(2, 2, vec![255u8; 12]).unwrap();
+        let result = reconstructor.reconstruct(vec![page]);
+        assert!(matches!(result, Err(PdfError::PdfCreation(_))));
+    }
+
     #[test]
     fn test_pixels_to_points() {
         let reconstructor = PdfReconstructor::new();
@@ -225,6 +759,148 @@ mod tests {
         assert!(pdf_data.starts_with(b"%PDF-"));
     }
 
+    #[test]
+    fn test_reconstruct_to_writer_matches_reconstruct() {
+        let reconstructor = PdfReconstructor::new();
+        let page = PageData::new(2, 2, vec![255u8; 12]).unwrap();
+
+        let mut buf = Vec::new();
+        reconstructor
+            .reconstruct_to_writer(vec![page], &mut buf)
+            .unwrap();
+
+        assert!(buf.starts_with(b"%PDF-"));
+    }
+
+    #[test]
+    fn test_verify_output_accepts_matching_pdf() {
+        let reconstructor = PdfReconstructor::new();
+        let pages = vec![
+            PageData::new(2, 2, vec![255u8; 12]).unwrap(),
+            PageData::new(3, 3, vec![0u8; 27]).unwrap(),
+        ];
+        let dimensions: Vec<(u16, u16)> = pages.iter().map(|p| (p.width, p.height)).collect();
+
+        let pdf_data = reconstructor.reconstruct(pages).unwrap();
+        assert!(reconstructor.verify_output(&pdf_data, &dimensions).is_ok());
+    }
+
+    #[test]
+    fn test_verify_output_rejects_page_count_mismatch() {
+        let reconstructor = PdfReconstructor::new();
+        let pages = vec![PageData::new(2, 2, vec![255u8; 12]).unwrap()];
+        let pdf_data = reconstructor.reconstruct(pages).unwrap();
+
+        let wrong_expected = vec![(2u16, 2u16), (2u16, 2u16)];
+        assert!(matches!(
+            reconstructor.verify_output(&pdf_data, &wrong_expected),
+            Err(PdfError::PdfCreation(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_output_rejects_garbage_bytes() {
+        let reconstructor = PdfReconstructor::new();
+        let dimensions = vec![(2u16, 2u16)];
+
+        assert!(matches!(
+            reconstructor.verify_output(b"not a pdf", &dimensions),
+            Err(PdfError::PdfCreation(_))
+        ));
+    }
+
+    #[test]
+    fn test_default_page_size_is_native() {
+        let reconstructor = PdfReconstructor::new();
+        assert_eq!(reconstructor.page_size, PaperSize::Native);
+    }
+
+    #[test]
+    fn test_a4_dimensions_mm() {
+        assert_eq!(PaperSize::A4.dimensions_mm(), Some((210.0, 297.0)));
+    }
+
+    #[test]
+    fn test_letter_landscape_swaps_dimensions() {
+        let portrait = PaperSize::Letter {
+            orientation: Orientation::Portrait,
+        }
+        .dimensions_mm()
+        .unwrap();
+        let landscape = PaperSize::Letter {
+            orientation: Orientation::Landscape,
+        }
+        .dimensions_mm()
+        .unwrap();
+        assert_eq!(portrait, (landscape.1, landscape.0));
+    }
+
+    #[test]
+    fn test_reconstruct_with_fit_to_a4_mixed_page_sizes() {
+        let reconstructor = PdfReconstructor::new().with_page_size(PaperSize::A4);
+
+        let page1 = PageData::new(100, 200, vec![0u8; 100 * 200 * 3]).unwrap();
+        let page2 = PageData::new(300, 50, vec![0u8; 300 * 50 * 3]).unwrap();
+
+        let result = reconstructor.reconstruct(vec![page1, page2]);
+        assert!(result.is_ok());
+        assert!(result.unwrap().starts_with(b"%PDF-"));
+    }
+
+    /// Extracts the `a`/`d` (x/y scale) operands of the content stream's `cm` operator, the
+    /// values printpdf computed from `ImageTransform::scale_x`/`scale_y` and `dpi`.
+    fn content_stream_cm_scale(doc: &lopdf::Document, page_id: lopdf::ObjectId) -> (f32, f32) {
+        let content = doc.get_page_content(page_id).unwrap();
+        let text = String::from_utf8(content).unwrap();
+        let cm_line = text
+            .lines()
+            .find(|line| line.trim_end().ends_with(" cm"))
+            .unwrap();
+        let operands: Vec<f32> = cm_line
+            .split_whitespace()
+            .take(4)
+            .map(|n| n.parse().unwrap())
+            .collect();
+        (operands[0], operands[3])
+    }
+
+    #[test]
+    fn test_native_mode_image_is_rendered_at_page_size_not_oversized() {
+        let reconstructor = PdfReconstructor::new();
+        let page = PageData::new(1000, 1000, vec![0u8; 1000 * 1000 * 3]).unwrap();
+
+        let pdf_data = reconstructor.reconstruct(vec![page]).unwrap();
+        let doc = lopdf::Document::load_from(pdf_data.as_slice()).unwrap();
+        let (_, page_id) = doc.get_pages().into_iter().next().unwrap();
+
+        // In native mode the image fills the page exactly, so the `cm` matrix scaling the
+        // 1x1 unit square up to the image's size must land on the page's own point size
+        // (not some DPI-relative multiple of it, which is what shipped before this fix).
+        let page_width_pt = reconstructor.pixels_to_points(1000);
+        let (scale_x, scale_y) = content_stream_cm_scale(&doc, page_id);
+        assert!((scale_x - page_width_pt).abs() < 1.0, "scale_x = {scale_x}");
+        assert!((scale_y - page_width_pt).abs() < 1.0, "scale_y = {scale_y}");
+    }
+
+    #[test]
+    fn test_a4_fit_image_is_scaled_to_fit_within_margins() {
+        let reconstructor = PdfReconstructor::new().with_page_size(PaperSize::A4);
+        let page = PageData::new(1000, 1000, vec![0u8; 1000 * 1000 * 3]).unwrap();
+
+        let pdf_data = reconstructor.reconstruct(vec![page]).unwrap();
+        let doc = lopdf::Document::load_from(pdf_data.as_slice()).unwrap();
+        let (_, page_id) = doc.get_pages().into_iter().next().unwrap();
+
+        // A4 is 210x297mm with 10mm margins on every side, so the available area is
+        // 190x277mm; a square image must be scaled to fit the narrower dimension (190mm),
+        // never drawn at its native DPI-relative size (which would be tens of page-widths
+        // across, as it was before this fix).
+        let (scale_x, scale_y) = content_stream_cm_scale(&doc, page_id);
+        let available_width_pt = 190.0 / 0.352778;
+        assert!(scale_x < available_width_pt + 1.0, "scale_x = {scale_x}");
+        assert!(scale_y < available_width_pt + 1.0, "scale_y = {scale_y}");
+    }
+
     #[test]
     fn test_reconstruct_multiple_pages() {
         let reconstructor = PdfReconstructor::new();
@@ -250,6 +926,7 @@ mod tests {
         let page = PageData {
             width: 0,
             height: 100,
+            color_mode: crate::stream_reader::ColorMode::Rgb,
             pixels: vec![],
         };
 
@@ -6,22 +6,100 @@
 //!   - Page width (2 bytes, big-endian int)
 //!   - Page height (2 bytes, big-endian int)
 //!   - Page data (width * height * 3 bytes, RGB pixels)
+//!
+//! Callers that opt into the versioned protocol (see [`PixelStreamReader::read_protocol_version`])
+//! get a one-byte color-mode field ahead of each page's width/height, allowing grayscale and
+//! RGBA pages alongside the original RGB-only wire format.
+//!
+//! Callers that opt into a compressed transport (see [`PixelStreamReader::read_codec`]) get
+//! each page's pixel payload zlib-compressed on the wire, prefixed with its compressed length
+//! (4 bytes, big-endian), and decompressed transparently back to the same
+//! `width * height * bytes_per_pixel` bytes the uncompressed protocol produces. The length
+//! prefix bounds exactly how many bytes belong to this page's compressed payload, since a
+//! `ZlibDecoder` reads ahead of the end of its deflate stream and would otherwise consume
+//! bytes belonging to the next page.
 
 use byteorder::{BigEndian, ReadBytesExt};
+use flate2::read::ZlibDecoder;
 use std::io::{self, Read};
 
+/// Compression codec applied to each page's pixel payload on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StreamCodec {
+    /// Pixel bytes are written as-is (the original format).
+    #[default]
+    Raw,
+    /// Pixel bytes are zlib-deflated and length-prefixed (4-byte big-endian compressed
+    /// length); `read_page` decompresses them transparently.
+    Zlib,
+}
+
+impl StreamCodec {
+    /// Decodes a codec byte from the wire format (0=Raw, 1=Zlib).
+    fn from_byte(byte: u8) -> Result<Self, StreamError> {
+        match byte {
+            0 => Ok(StreamCodec::Raw),
+            1 => Ok(StreamCodec::Zlib),
+            other => Err(StreamError::UnknownCodec(other)),
+        }
+    }
+}
+
+/// Pixel color mode of a page, as carried by the versioned stream protocol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    /// 1 byte per pixel.
+    Grayscale,
+    /// 3 bytes per pixel (the original, implicit format).
+    Rgb,
+    /// 4 bytes per pixel.
+    Rgba,
+}
+
+impl ColorMode {
+    /// Number of raw bytes used to encode a single pixel in this mode.
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            ColorMode::Grayscale => 1,
+            ColorMode::Rgb => 3,
+            ColorMode::Rgba => 4,
+        }
+    }
+
+    /// Decodes a color-mode byte from the wire format (0=Grayscale, 1=RGB, 2=RGBA).
+    fn from_byte(byte: u8) -> Result<Self, StreamError> {
+        match byte {
+            0 => Ok(ColorMode::Grayscale),
+            1 => Ok(ColorMode::Rgb),
+            2 => Ok(ColorMode::Rgba),
+            other => Err(StreamError::UnknownColorMode(other)),
+        }
+    }
+}
+
 /// Represents a page with its dimensions and pixel data.
 #[derive(Debug, Clone, PartialEq)]
 pub struct PageData {
     pub width: u16,
     pub height: u16,
+    pub color_mode: ColorMode,
     pub pixels: Vec<u8>,
 }
 
 impl PageData {
-    /// Creates a new PageData instance.
+    /// Creates a new RGB PageData instance.
     pub fn new(width: u16, height: u16, pixels: Vec<u8>) -> Result<Self, StreamError> {
-        let expected_size = (width as usize) * (height as usize) * 3;
+        Self::with_color_mode(width, height, ColorMode::Rgb, pixels)
+    }
+
+    /// Creates a new PageData instance with an explicit color mode.
+    pub fn with_color_mode(
+        width: u16,
+        height: u16,
+        color_mode: ColorMode,
+        pixels: Vec<u8>,
+    ) -> Result<Self, StreamError> {
+        let expected_size = (width as usize) * (height as usize) * color_mode.bytes_per_pixel();
         if pixels.len() != expected_size {
             return Err(StreamError::InvalidPixelData {
                 expected: expected_size,
@@ -31,6 +109,7 @@ impl PageData {
         Ok(PageData {
             width,
             height,
+            color_mode,
             pixels,
         })
     }
@@ -58,17 +137,108 @@ pub enum StreamError {
 
     #[error("Unexpected end of stream")]
     UnexpectedEof,
+
+    #[error("Page dimensions {width}x{height} exceed the maximum of {max}x{max}")]
+    DimensionTooLarge { width: u16, height: u16, max: u16 },
+
+    #[error("Stream declares {count} pages, exceeding the maximum of {max}")]
+    TooManyPages { count: u16, max: u16 },
+
+    #[error("Stream size {total} bytes exceeds the maximum of {max} bytes")]
+    StreamTooLarge { total: u64, max: u64 },
+
+    #[error("Unknown color mode byte: {0}")]
+    UnknownColorMode(u8),
+
+    #[error("Single page size {bytes} bytes exceeds the maximum of {max} bytes")]
+    PageTooLarge { bytes: u64, max: u64 },
+
+    #[error("Unknown stream codec byte: {0}")]
+    UnknownCodec(u8),
+
+    #[error("stream failed after {} good page(s): {source}", pages_read.len())]
+    Partial {
+        pages_read: Vec<PageData>,
+        source: Box<StreamError>,
+    },
+}
+
+/// Sanity limits enforced against untrusted stream input before allocating buffers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamLimits {
+    /// Maximum number of pages a stream may declare.
+    pub max_pages: u16,
+    /// Maximum width or height of a single page, in pixels.
+    pub max_dimension: u16,
+    /// Maximum decoded pixel bytes for a single page.
+    ///
+    /// Complements `max_dimension`: a page can have both width and height within
+    /// `max_dimension` individually while its `width * height * bytes_per_pixel` product
+    /// is still enormous (e.g. a square page at the dimension ceiling in RGBA).
+    pub max_page_bytes: u64,
+    /// Maximum cumulative decoded pixel bytes across all pages.
+    pub max_total_bytes: u64,
+}
+
+impl Default for StreamLimits {
+    /// Defaults generous enough for any realistic scanned document, while still
+    /// rejecting a crafted stream that declares e.g. a 65535x65535 page.
+    fn default() -> Self {
+        StreamLimits {
+            max_pages: 10_000,
+            max_dimension: 20_000,
+            max_page_bytes: 256 * 1024 * 1024, // 256 MiB
+            max_total_bytes: 1_024 * 1024 * 1024, // 1 GiB
+        }
+    }
 }
 
 /// Reads pixel stream data from a container's stdout.
 pub struct PixelStreamReader<R: Read> {
     reader: R,
+    limits: StreamLimits,
+    total_bytes_read: u64,
+    color_mode_enabled: bool,
+    codec: StreamCodec,
 }
 
 impl<R: Read> PixelStreamReader<R> {
-    /// Creates a new PixelStreamReader.
+    /// Creates a new PixelStreamReader with the default sanity limits.
     pub fn new(reader: R) -> Self {
-        PixelStreamReader { reader }
+        Self::with_limits(reader, StreamLimits::default())
+    }
+
+    /// Creates a new PixelStreamReader with custom sanity limits.
+    pub fn with_limits(reader: R, limits: StreamLimits) -> Self {
+        PixelStreamReader {
+            reader,
+            limits,
+            total_bytes_read: 0,
+            color_mode_enabled: false,
+            codec: StreamCodec::default(),
+        }
+    }
+
+    /// Reads the one-byte protocol version from the head of the stream.
+    ///
+    /// Version 0 is the original, implicit-RGB wire format. Version 1 and above prefix
+    /// each page with a color-mode byte (see [`ColorMode`]), enabling grayscale and RGBA
+    /// pages. Must be called, if at all, before [`read_page_count`](Self::read_page_count).
+    pub fn read_protocol_version(&mut self) -> Result<u8, StreamError> {
+        let version = self.reader.read_u8()?;
+        self.color_mode_enabled = version >= 1;
+        Ok(version)
+    }
+
+    /// Reads the one-byte codec indicator from the head of the stream.
+    ///
+    /// Selects how each page's pixel payload is encoded on the wire (see [`StreamCodec`]).
+    /// Optional: callers that never call this get the original raw, uncompressed payload.
+    /// Must be called, if at all, before [`read_page_count`](Self::read_page_count).
+    pub fn read_codec(&mut self) -> Result<StreamCodec, StreamError> {
+        let codec = StreamCodec::from_byte(self.reader.read_u8()?)?;
+        self.codec = codec;
+        Ok(codec)
     }
 
     /// Reads the page count from the stream.
@@ -77,46 +247,179 @@ impl<R: Read> PixelStreamReader<R> {
         if count == 0 {
             return Err(StreamError::InvalidPageCount(count));
         }
+        if count > self.limits.max_pages {
+            return Err(StreamError::TooManyPages {
+                count,
+                max: self.limits.max_pages,
+            });
+        }
         Ok(count)
     }
 
     /// Reads a single page from the stream.
     pub fn read_page(&mut self) -> Result<PageData, StreamError> {
+        let color_mode = if self.color_mode_enabled {
+            ColorMode::from_byte(self.reader.read_u8()?)?
+        } else {
+            ColorMode::Rgb
+        };
+
         let width = self.reader.read_u16::<BigEndian>()?;
         let height = self.reader.read_u16::<BigEndian>()?;
 
         if width == 0 || height == 0 {
             return Err(StreamError::InvalidPageDimensions { width, height });
         }
+        if width > self.limits.max_dimension || height > self.limits.max_dimension {
+            return Err(StreamError::DimensionTooLarge {
+                width,
+                height,
+                max: self.limits.max_dimension,
+            });
+        }
+
+        let num_bytes = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|px| px.checked_mul(color_mode.bytes_per_pixel()))
+            .ok_or(StreamError::DimensionTooLarge {
+                width,
+                height,
+                max: self.limits.max_dimension,
+            })?;
+
+        if num_bytes as u64 > self.limits.max_page_bytes {
+            return Err(StreamError::PageTooLarge {
+                bytes: num_bytes as u64,
+                max: self.limits.max_page_bytes,
+            });
+        }
+
+        self.total_bytes_read = self
+            .total_bytes_read
+            .checked_add(num_bytes as u64)
+            .ok_or(StreamError::StreamTooLarge {
+                total: u64::MAX,
+                max: self.limits.max_total_bytes,
+            })?;
+        if self.total_bytes_read > self.limits.max_total_bytes {
+            return Err(StreamError::StreamTooLarge {
+                total: self.total_bytes_read,
+                max: self.limits.max_total_bytes,
+            });
+        }
 
-        let num_bytes = (width as usize) * (height as usize) * 3;
         let mut pixels = vec![0u8; num_bytes];
-        self.reader.read_exact(&mut pixels).map_err(|e| {
+        let as_stream_error = |e: io::Error| {
             if e.kind() == io::ErrorKind::UnexpectedEof {
                 StreamError::UnexpectedEof
             } else {
                 StreamError::Io(e)
             }
-        })?;
+        };
+
+        match self.codec {
+            StreamCodec::Raw => self.reader.read_exact(&mut pixels).map_err(as_stream_error)?,
+            StreamCodec::Zlib => {
+                // The compressed payload is length-prefixed: a `ZlibDecoder` pulls extra
+                // bytes into its own internal buffer past the end of the deflate stream, so
+                // decoding directly off the shared reader would silently eat into the next
+                // page. Reading exactly `compressed_len` bytes into an owned buffer first,
+                // then decoding that buffer in isolation, keeps the shared reader's position
+                // exactly at the next page's header.
+                let compressed_len =
+                    self.reader.read_u32::<BigEndian>().map_err(as_stream_error)? as u64;
+                if compressed_len > self.limits.max_page_bytes {
+                    return Err(StreamError::PageTooLarge {
+                        bytes: compressed_len,
+                        max: self.limits.max_page_bytes,
+                    });
+                }
+                let mut compressed = vec![0u8; compressed_len as usize];
+                self.reader
+                    .read_exact(&mut compressed)
+                    .map_err(as_stream_error)?;
+                ZlibDecoder::new(io::Cursor::new(compressed))
+                    .read_exact(&mut pixels)
+                    .map_err(as_stream_error)?
+            }
+        };
 
         Ok(PageData {
             width,
             height,
+            color_mode,
             pixels,
         })
     }
 
     /// Reads all pages from the stream.
+    ///
+    /// Buffers every page in memory before returning; for large or untrusted documents
+    /// prefer [`pages`](Self::pages), which yields one page at a time. If a page fails
+    /// after at least one page was already read successfully, the successfully-read pages
+    /// are not discarded: the error is returned as [`StreamError::Partial`] carrying them
+    /// alongside the underlying cause, so a caller can still reconstruct a best-effort
+    /// document from the pages read before the failure.
     pub fn read_all_pages(&mut self) -> Result<Vec<PageData>, StreamError> {
         let page_count = self.read_page_count()?;
         let mut pages = Vec::with_capacity(page_count as usize);
 
         for _ in 0..page_count {
-            pages.push(self.read_page()?);
+            match self.read_page() {
+                Ok(page) => pages.push(page),
+                Err(e) if pages.is_empty() => return Err(e),
+                Err(e) => {
+                    return Err(StreamError::Partial {
+                        pages_read: pages,
+                        source: Box::new(e),
+                    })
+                }
+            }
         }
 
         Ok(pages)
     }
+
+    /// Reads the page count, then returns an iterator yielding one decoded page at a time.
+    ///
+    /// Unlike [`read_all_pages`](Self::read_all_pages), this never holds more than one
+    /// page's pixels in memory at once, so a caller can reconstruct and drop each page as
+    /// it arrives, keeping peak memory bounded regardless of document size. The iterator
+    /// stops and yields no further items after the first `Err`.
+    pub fn pages(&mut self) -> Result<Pages<'_, R>, StreamError> {
+        let remaining = self.read_page_count()?;
+        Ok(Pages {
+            reader: self,
+            remaining,
+            done: false,
+        })
+    }
+}
+
+/// Iterator over the pages of a stream, returned by [`PixelStreamReader::pages`].
+pub struct Pages<'a, R: Read> {
+    reader: &'a mut PixelStreamReader<R>,
+    remaining: u16,
+    done: bool,
+}
+
+impl<'a, R: Read> Iterator for Pages<'a, R> {
+    type Item = Result<PageData, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        match self.reader.read_page() {
+            Ok(page) => Some(Ok(page)),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -249,4 +552,293 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn test_page_count_exceeds_limit() {
+        let data = vec![0xFF, 0xFF]; // 65535 pages
+        let limits = StreamLimits {
+            max_pages: 100,
+            ..StreamLimits::default()
+        };
+        let mut reader = PixelStreamReader::with_limits(Cursor::new(data), limits);
+
+        assert!(matches!(
+            reader.read_page_count(),
+            Err(StreamError::TooManyPages {
+                count: 0xFFFF,
+                max: 100
+            })
+        ));
+    }
+
+    #[test]
+    fn test_page_dimension_exceeds_limit() {
+        let data = create_test_stream(1, vec![(0xFFFF, 0xFFFF, vec![])]);
+        let limits = StreamLimits {
+            max_dimension: 1000,
+            ..StreamLimits::default()
+        };
+        let mut reader = PixelStreamReader::with_limits(Cursor::new(data), limits);
+        reader.read_page_count().unwrap();
+
+        assert!(matches!(
+            reader.read_page(),
+            Err(StreamError::DimensionTooLarge { max: 1000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_stream_total_bytes_exceeds_limit() {
+        let pixels = vec![0u8; 300];
+        let data = create_test_stream(2, vec![(10, 10, pixels.clone()), (10, 10, pixels)]);
+        let limits = StreamLimits {
+            max_total_bytes: 400,
+            ..StreamLimits::default()
+        };
+        let mut reader = PixelStreamReader::with_limits(Cursor::new(data), limits);
+
+        // The first page fits under the limit, so the failure on the second page is
+        // reported as Partial, carrying the one good page read so far.
+        match reader.read_all_pages() {
+            Err(StreamError::Partial { pages_read, source }) => {
+                assert_eq!(pages_read.len(), 1);
+                assert!(matches!(*source, StreamError::StreamTooLarge { max: 400, .. }));
+            }
+            other => panic!("expected Partial error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_all_pages_fails_directly_when_first_page_fails() {
+        let data = create_test_stream(1, vec![(0xFFFF, 0xFFFF, vec![])]);
+        let limits = StreamLimits {
+            max_dimension: 1000,
+            ..StreamLimits::default()
+        };
+        let mut reader = PixelStreamReader::with_limits(Cursor::new(data), limits);
+
+        // No pages were read yet, so the error is not wrapped in Partial.
+        assert!(matches!(
+            reader.read_all_pages(),
+            Err(StreamError::DimensionTooLarge { max: 1000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_default_limits_allow_normal_pages() {
+        let mut reader = PixelStreamReader::new(Cursor::new(create_test_stream(
+            1,
+            vec![(2, 2, vec![0u8; 12])],
+        )));
+        assert!(reader.read_all_pages().is_ok());
+    }
+
+    #[test]
+    fn test_color_mode_bytes_per_pixel() {
+        assert_eq!(ColorMode::Grayscale.bytes_per_pixel(), 1);
+        assert_eq!(ColorMode::Rgb.bytes_per_pixel(), 3);
+        assert_eq!(ColorMode::Rgba.bytes_per_pixel(), 4);
+    }
+
+    #[test]
+    fn test_legacy_stream_defaults_to_rgb() {
+        let data = create_test_stream(1, vec![(2, 2, vec![255u8; 12])]);
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+
+        let pages = reader.read_all_pages().unwrap();
+        assert_eq!(pages[0].color_mode, ColorMode::Rgb);
+    }
+
+    #[test]
+    fn test_versioned_stream_reads_grayscale_page() {
+        let mut data = vec![1u8]; // protocol version 1
+        data.extend_from_slice(&1u16.to_be_bytes()); // page count
+        data.push(0); // color mode: Grayscale
+        data.extend_from_slice(&2u16.to_be_bytes()); // width
+        data.extend_from_slice(&2u16.to_be_bytes()); // height
+        data.extend_from_slice(&[128u8; 4]); // 2x2 grayscale pixels
+
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+        assert_eq!(reader.read_protocol_version().unwrap(), 1);
+
+        let pages = reader.read_all_pages().unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].color_mode, ColorMode::Grayscale);
+        assert_eq!(pages[0].pixels.len(), 4);
+    }
+
+    #[test]
+    fn test_versioned_stream_reads_rgba_page() {
+        let mut data = vec![1u8]; // protocol version 1
+        data.extend_from_slice(&1u16.to_be_bytes()); // page count
+        data.push(2); // color mode: RGBA
+        data.extend_from_slice(&2u16.to_be_bytes()); // width
+        data.extend_from_slice(&2u16.to_be_bytes()); // height
+        data.extend_from_slice(&[255u8; 16]); // 2x2 RGBA pixels
+
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+        reader.read_protocol_version().unwrap();
+
+        let pages = reader.read_all_pages().unwrap();
+        assert_eq!(pages[0].color_mode, ColorMode::Rgba);
+        assert_eq!(pages[0].pixels.len(), 16);
+    }
+
+    #[test]
+    fn test_unknown_color_mode_byte() {
+        let mut data = vec![1u8]; // protocol version 1
+        data.extend_from_slice(&1u16.to_be_bytes()); // page count
+        data.push(9); // unknown color mode
+
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+        reader.read_protocol_version().unwrap();
+        reader.read_page_count().unwrap();
+
+        assert!(matches!(
+            reader.read_page(),
+            Err(StreamError::UnknownColorMode(9))
+        ));
+    }
+
+    #[test]
+    fn test_page_data_with_color_mode_invalid_size() {
+        let result = PageData::with_color_mode(2, 2, ColorMode::Grayscale, vec![0u8; 12]);
+        assert!(matches!(
+            result,
+            Err(StreamError::InvalidPixelData {
+                expected: 4,
+                actual: 12
+            })
+        ));
+    }
+
+    #[test]
+    fn test_single_page_exceeds_max_page_bytes() {
+        let pixels = vec![0u8; 300];
+        let data = create_test_stream(1, vec![(10, 10, pixels)]);
+        let limits = StreamLimits {
+            max_page_bytes: 200,
+            ..StreamLimits::default()
+        };
+        let mut reader = PixelStreamReader::with_limits(Cursor::new(data), limits);
+        reader.read_page_count().unwrap();
+
+        assert!(matches!(
+            reader.read_page(),
+            Err(StreamError::PageTooLarge { bytes: 300, max: 200 })
+        ));
+    }
+
+    #[test]
+    fn test_pages_iterator_yields_each_page() {
+        let data = create_test_stream(
+            2,
+            vec![(2, 2, vec![0u8; 12]), (1, 1, vec![255u8; 3])],
+        );
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+
+        let pages: Vec<PageData> = reader
+            .pages()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].width, 2);
+        assert_eq!(pages[1].width, 1);
+    }
+
+    #[test]
+    fn test_pages_iterator_stops_after_error() {
+        let data = create_test_stream(2, vec![(0, 0, vec![])]);
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+
+        let mut iter = reader.pages().unwrap();
+        assert!(matches!(
+            iter.next(),
+            Some(Err(StreamError::InvalidPageDimensions { width: 0, height: 0 }))
+        ));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_raw_codec_is_default() {
+        let mut reader = PixelStreamReader::new(Cursor::new(create_test_stream(
+            1,
+            vec![(2, 2, vec![7u8; 12])],
+        )));
+        let pages = reader.read_all_pages().unwrap();
+        assert_eq!(pages[0].pixels, vec![7u8; 12]);
+    }
+
+    #[test]
+    fn test_unknown_codec_byte() {
+        let data = vec![9u8]; // unknown codec
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+        assert!(matches!(
+            reader.read_codec(),
+            Err(StreamError::UnknownCodec(9))
+        ));
+    }
+
+    #[test]
+    fn test_zlib_codec_round_trip() {
+        use std::io::Write;
+
+        let raw_pixels = vec![42u8; 12]; // 2x2 RGB
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&raw_pixels).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut data = vec![1u8]; // codec: Zlib
+        data.extend_from_slice(&1u16.to_be_bytes()); // page count
+        data.extend_from_slice(&2u16.to_be_bytes()); // width
+        data.extend_from_slice(&2u16.to_be_bytes()); // height
+        data.extend_from_slice(&(compressed.len() as u32).to_be_bytes()); // compressed length
+        data.extend_from_slice(&compressed);
+
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+        assert_eq!(reader.read_codec().unwrap(), StreamCodec::Zlib);
+
+        let pages = reader.read_all_pages().unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].pixels, raw_pixels);
+    }
+
+    #[test]
+    fn test_zlib_codec_multiple_pages() {
+        use std::io::Write;
+
+        let compress = |bytes: &[u8]| -> Vec<u8> {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes).unwrap();
+            encoder.finish().unwrap()
+        };
+
+        let page_one_pixels = vec![1u8; 12]; // 2x2 RGB
+        let page_two_pixels = vec![2u8; 3]; // 1x1 RGB
+
+        let page_one_compressed = compress(&page_one_pixels);
+        let page_two_compressed = compress(&page_two_pixels);
+
+        let mut data = vec![1u8]; // codec: Zlib
+        data.extend_from_slice(&2u16.to_be_bytes()); // page count
+        data.extend_from_slice(&2u16.to_be_bytes());
+        data.extend_from_slice(&2u16.to_be_bytes());
+        data.extend_from_slice(&(page_one_compressed.len() as u32).to_be_bytes());
+        data.extend_from_slice(&page_one_compressed);
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(&(page_two_compressed.len() as u32).to_be_bytes());
+        data.extend_from_slice(&page_two_compressed);
+
+        let mut reader = PixelStreamReader::new(Cursor::new(data));
+        reader.read_codec().unwrap();
+
+        let pages = reader.read_all_pages().unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].pixels, page_one_pixels);
+        assert_eq!(pages[1].pixels, page_two_pixels);
+    }
 }